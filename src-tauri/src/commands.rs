@@ -1,9 +1,12 @@
-use crate::{config::Config, security, AppState};
+use crate::{config::Config, journal, security, AppState};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Manager, State};
 use anyhow::Result;
 
+/// Minimum Python version the bundled agent requires.
+const MIN_PYTHON_VERSION: (u32, u32) = (3, 8);
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VoiceCommand {
     pub text: String,
@@ -21,6 +24,12 @@ pub struct OperationRecord {
     pub can_undo: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PythonInfo {
+    pub path: String,
+    pub version: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SystemInfo {
     pub platform: String,
@@ -30,18 +39,99 @@ pub struct SystemInfo {
     pub downloads_dir: String,
 }
 
-#[tauri::command]
-pub async fn execute_voice_command(
+/// What the Python agent parsed a command into, before anything runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationPreview {
+    pub operation_type: String,
+    pub affected_paths: Vec<String>,
+    pub is_directory: bool,
+    /// Destination for each entry in `affected_paths`, for move/rename
+    /// operations. Empty when the operation type has no destination (delete)
+    /// or the agent doesn't report one.
+    #[serde(default)]
+    pub target_paths: Vec<String>,
+}
+
+/// The outcome of trying to run a voice command, once it has actually been
+/// decided. `Err` is reserved for transport/system failures (server
+/// unreachable, bad response) — everything the app itself decides comes
+/// back as `Ok` so the frontend and history can tell those cases apart.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status")]
+pub enum ExecutionOutcome {
+    /// The operation ran and produced a record.
+    Completed(OperationRecord),
+    /// Risky enough to need sign-off; call `confirm_operation(token)` to run it
+    /// or `cancel_operation(token)` to drop it. Expires on its own either way.
+    PendingConfirmation {
+        token: String,
+        operation_type: String,
+        affected_paths: Vec<String>,
+        risk_level: String,
+    },
+    /// Blocked by policy (e.g. `validate_path` rejected an affected path).
+    Denied { reason: String },
+    /// The user (or an expired token) called it off before it ran.
+    Cancelled { reason: String },
+}
+
+/// A risky operation awaiting `confirm_operation`/`cancel_operation`.
+pub(crate) struct PendingOperation {
     command: String,
-    state: State<'_, AppState>,
-) -> Result<OperationRecord, String> {
-    log::info!("Executing voice command: {}", command);
+    preview: OperationPreview,
+    risk: security::RiskLevel,
+    created_at: std::time::Instant,
+}
+
+/// How long a confirmation token stays valid before it's treated as expired.
+const CONFIRMATION_TOKEN_TTL: std::time::Duration = std::time::Duration::from_secs(120);
+
+fn generate_confirmation_token() -> String {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let counter = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("{:x}-{:x}", nanos, counter)
+}
+
+/// Drop any tokens that have outlived [`CONFIRMATION_TOKEN_TTL`].
+async fn prune_expired_confirmations(state: &AppState) {
+    let mut pending = state.pending_confirmations.lock().await;
+    pending.retain(|_, op| op.created_at.elapsed() < CONFIRMATION_TOKEN_TTL);
+}
+
+async fn preview_command(base_url: &str, command: &str) -> Result<OperationPreview, String> {
+    let client = reqwest::Client::new();
 
-    let url = format!("{}/execute", state.python_server_url);
+    let response = client
+        .post(format!("{}/preview", base_url))
+        .json(&serde_json::json!({
+            "command": command,
+            "timestamp": chrono::Utc::now().timestamp()
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to preview command: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Server error: {}", response.status()));
+    }
+
+    response
+        .json::<OperationPreview>()
+        .await
+        .map_err(|e| format!("Failed to parse preview response: {}", e))
+}
+
+async fn run_command(base_url: &str, command: &str) -> Result<OperationRecord, String> {
     let client = reqwest::Client::new();
 
     let response = client
-        .post(&url)
+        .post(format!("{}/execute", base_url))
         .json(&serde_json::json!({
             "command": command,
             "timestamp": chrono::Utc::now().timestamp()
@@ -54,50 +144,302 @@ pub async fn execute_voice_command(
         return Err(format!("Server error: {}", response.status()));
     }
 
-    let result: OperationRecord = response
-        .json()
+    response
+        .json::<OperationRecord>()
         .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
+        .map_err(|e| format!("Failed to parse response: {}", e))
+}
+
+/// Run `command`, journaling it if its risk warrants recoverability.
+///
+/// Low-risk operations are simply forwarded to the agent. For `Medium`+ risk,
+/// `delete`/`remove` operations are performed locally by moving the affected
+/// paths into the trash directory and are never sent to the agent (rather
+/// than trusting it to keep them recoverable), journaled under an
+/// id we mint ourselves since there's no server-assigned id to reconcile
+/// with. Move/rename/copy operations are still carried out by the agent, so
+/// we run them first and journal the source/target paths under the id the
+/// agent's `OperationRecord` comes back with — using our own id there would
+/// leave `undo_operation`/`get_operation_history` unable to match the local
+/// journal entry to the id the frontend actually sees.
+async fn journal_and_run(
+    base_url: &str,
+    command: &str,
+    preview: &OperationPreview,
+    risk: security::RiskLevel,
+) -> Result<ExecutionOutcome, String> {
+    if matches!(risk, security::RiskLevel::Low) {
+        return run_command(base_url, command).await.map(ExecutionOutcome::Completed);
+    }
+
+    let entry_id = journal::new_entry_id();
+    let source_paths: Vec<PathBuf> = preview.affected_paths.iter().map(PathBuf::from).collect();
+    let is_delete = matches!(preview.operation_type.to_lowercase().as_str(), "delete" | "remove");
+
+    if is_delete {
+        let mut trashed = Vec::new();
+        for path in &source_paths {
+            match journal::move_to_trash(&entry_id, path) {
+                Ok(t) => trashed.push(t),
+                Err(e) => {
+                    // Put back anything already trashed so a partial
+                    // failure doesn't leave some files moved and others not.
+                    for t in &trashed {
+                        let _ = std::fs::rename(&t.trash_path, &t.original_path);
+                    }
+                    return Err(format!("Failed to move {:?} to trash: {}", path, e));
+                }
+            }
+        }
+
+        let entry = journal::record(
+            &entry_id,
+            command,
+            &preview.operation_type,
+            source_paths,
+            Vec::new(),
+            trashed,
+        )
+        .map_err(|e| e.to_string())?;
+
+        return Ok(ExecutionOutcome::Completed(OperationRecord {
+            id: entry.id,
+            command: command.to_string(),
+            operation_type: preview.operation_type.clone(),
+            files_affected: preview.affected_paths.clone(),
+            timestamp: entry.timestamp,
+            status: "completed".to_string(),
+            can_undo: true,
+        }));
+    }
 
-    Ok(result)
+    let target_paths: Vec<PathBuf> = preview.target_paths.iter().map(PathBuf::from).collect();
+    let record = run_command(base_url, command).await?;
+
+    journal::record(
+        &record.id,
+        command,
+        &preview.operation_type,
+        source_paths,
+        target_paths,
+        Vec::new(),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(ExecutionOutcome::Completed(record))
 }
 
+/// Preview what a command would do without running it, so the frontend can
+/// show the user what's about to happen (and this side can compute its risk
+/// level) before anything touches disk.
+#[tauri::command]
+pub async fn preview_voice_command(
+    command: String,
+    state: State<'_, AppState>,
+) -> Result<OperationPreview, String> {
+    let base_url = state.python_server_url.lock().await.clone();
+    preview_command(&base_url, &command).await
+}
+
+#[tauri::command]
+pub async fn execute_voice_command(
+    command: String,
+    state: State<'_, AppState>,
+) -> Result<ExecutionOutcome, String> {
+    log::info!("Executing voice command: {}", command);
+
+    let base_url = state.python_server_url.lock().await.clone();
+    let preview = preview_command(&base_url, &command).await?;
+
+    let allowed_directories = {
+        let config = state.config.lock().await;
+        config.allowed_directories.clone()
+    };
+    for affected in &preview.affected_paths {
+        if !security::validate_path(Path::new(affected), &allowed_directories) {
+            return Ok(ExecutionOutcome::Denied {
+                reason: format!("Path is outside the allowed directories: {}", affected),
+            });
+        }
+    }
+    for target in &preview.target_paths {
+        if !security::validate_destination_path(Path::new(target), &allowed_directories) {
+            return Ok(ExecutionOutcome::Denied {
+                reason: format!("Destination is outside the allowed directories: {}", target),
+            });
+        }
+    }
+
+    let risk = security::get_operation_risk_level(
+        &preview.operation_type,
+        preview.affected_paths.len(),
+        preview.is_directory,
+    );
+
+    let confirmation_required = state.config.lock().await.confirmation_required;
+    let needs_confirmation =
+        confirmation_required && matches!(risk, security::RiskLevel::High | security::RiskLevel::Critical);
+
+    if needs_confirmation {
+        prune_expired_confirmations(&state).await;
+
+        let token = generate_confirmation_token();
+        state.pending_confirmations.lock().await.insert(
+            token.clone(),
+            PendingOperation {
+                command,
+                preview: preview.clone(),
+                risk,
+                created_at: std::time::Instant::now(),
+            },
+        );
+
+        return Ok(ExecutionOutcome::PendingConfirmation {
+            token,
+            operation_type: preview.operation_type,
+            affected_paths: preview.affected_paths,
+            risk_level: format!("{:?}", risk),
+        });
+    }
+
+    journal_and_run(&base_url, &command, &preview, risk).await
+}
+
+/// Run an operation that was held back for confirmation. Single-use: the
+/// token is removed whether it succeeds, is denied, or has expired.
+#[tauri::command]
+pub async fn confirm_operation(
+    token: String,
+    state: State<'_, AppState>,
+) -> Result<ExecutionOutcome, String> {
+    prune_expired_confirmations(&state).await;
+
+    let pending = state.pending_confirmations.lock().await.remove(&token);
+    let pending = match pending {
+        Some(p) => p,
+        None => {
+            return Ok(ExecutionOutcome::Cancelled {
+                reason: "Confirmation token not found or expired".to_string(),
+            })
+        }
+    };
+
+    // The token can sit for up to CONFIRMATION_TOKEN_TTL before it's used, so
+    // re-check the paths against the *current* allowed_directories rather
+    // than trusting the decision `execute_voice_command` made at preview
+    // time — a directory removed via `remove_allowed_directory` in between
+    // must still block the operation here.
+    let allowed_directories = {
+        let config = state.config.lock().await;
+        config.allowed_directories.clone()
+    };
+    for affected in &pending.preview.affected_paths {
+        if !security::validate_path(Path::new(affected), &allowed_directories) {
+            return Ok(ExecutionOutcome::Denied {
+                reason: format!("Path is outside the allowed directories: {}", affected),
+            });
+        }
+    }
+    for target in &pending.preview.target_paths {
+        if !security::validate_destination_path(Path::new(target), &allowed_directories) {
+            return Ok(ExecutionOutcome::Denied {
+                reason: format!("Destination is outside the allowed directories: {}", target),
+            });
+        }
+    }
+
+    let base_url = state.python_server_url.lock().await.clone();
+    journal_and_run(&base_url, &pending.command, &pending.preview, pending.risk).await
+}
+
+/// Explicitly decline a pending operation instead of letting its token expire.
+#[tauri::command]
+pub async fn cancel_operation(
+    token: String,
+    state: State<'_, AppState>,
+) -> Result<ExecutionOutcome, String> {
+    let removed = state.pending_confirmations.lock().await.remove(&token);
+    Ok(ExecutionOutcome::Cancelled {
+        reason: if removed.is_some() {
+            "Cancelled by user".to_string()
+        } else {
+            "Confirmation token not found or already expired".to_string()
+        },
+    })
+}
+
+/// Convert journaled operations into the same shape the agent's history
+/// endpoint returns, so the frontend doesn't need to know which source a
+/// record came from.
+fn local_history_records() -> Result<Vec<OperationRecord>, String> {
+    let entries = journal::read_entries().map_err(|e| e.to_string())?;
+    Ok(entries
+        .into_iter()
+        .map(|entry| OperationRecord {
+            id: entry.id,
+            command: entry.command,
+            operation_type: entry.operation_type,
+            files_affected: entry
+                .source_paths
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect(),
+            timestamp: entry.timestamp,
+            status: if entry.reverted { "reverted" } else { "completed" }.to_string(),
+            can_undo: !entry.reverted,
+        })
+        .collect())
+}
+
+/// History is served from the local journal first (so it works even when
+/// the agent is down), merged with whatever the agent also reports for
+/// operations it handled entirely on its own.
 #[tauri::command]
 pub async fn get_operation_history(
     limit: Option<usize>,
     state: State<'_, AppState>,
 ) -> Result<Vec<OperationRecord>, String> {
-    let url = format!(
-        "{}/history?limit={}",
-        state.python_server_url,
-        limit.unwrap_or(50)
-    );
-    let client = reqwest::Client::new();
+    let limit = limit.unwrap_or(50);
+    let mut records = local_history_records()?;
 
-    let response = client
-        .get(&url)
+    let base_url = state.python_server_url.lock().await.clone();
+    let client = reqwest::Client::new();
+    if let Ok(response) = client
+        .get(format!("{}/history?limit={}", base_url, limit))
         .send()
         .await
-        .map_err(|e| format!("Failed to fetch history: {}", e))?;
-
-    let history: Vec<OperationRecord> = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse history: {}", e))?;
+    {
+        if let Ok(server_records) = response.json::<Vec<OperationRecord>>().await {
+            let local_ids: std::collections::HashSet<_> =
+                records.iter().map(|r| r.id.clone()).collect();
+            records.extend(server_records.into_iter().filter(|r| !local_ids.contains(&r.id)));
+        }
+    }
 
-    Ok(history)
+    records.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    records.truncate(limit);
+    Ok(records)
 }
 
+/// Undo a journaled operation locally (restoring trashed files or reversing
+/// a move) when we have a record of it, falling back to asking the agent
+/// for operations it tracked entirely on its own.
 #[tauri::command]
 pub async fn undo_operation(
     operation_id: String,
     state: State<'_, AppState>,
 ) -> Result<String, String> {
-    let url = format!("{}/undo/{}", state.python_server_url, operation_id);
+    let entries = journal::read_entries().map_err(|e| e.to_string())?;
+    if let Some(entry) = entries.into_iter().find(|e| e.id == operation_id && !e.reverted) {
+        journal::revert(&entry).map_err(|e| e.to_string())?;
+        return Ok("Operation undone successfully".to_string());
+    }
+
+    let base_url = state.python_server_url.lock().await.clone();
     let client = reqwest::Client::new();
 
     let response = client
-        .post(&url)
+        .post(format!("{}/undo/{}", base_url, operation_id))
         .send()
         .await
         .map_err(|e| format!("Failed to undo operation: {}", e))?;
@@ -118,19 +460,41 @@ pub async fn get_settings(state: State<'_, AppState>) -> Result<Config, String>
 #[tauri::command]
 pub async fn update_settings(
     new_config: Config,
+    app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
+    let (hotkey_changed, allowed_directories_changed) = {
+        let config = state.config.lock().await;
+        (
+            config.hotkey != new_config.hotkey,
+            config.allowed_directories != new_config.allowed_directories,
+        )
+    };
+
+    if hotkey_changed {
+        crate::tray::update_hotkey(&app, &new_config.hotkey).await?;
+    }
+
     let mut config = state.config.lock().await;
     *config = new_config.clone();
     new_config
         .save()
         .map_err(|e| format!("Failed to save config: {}", e))?;
+
+    // A settings update can replace allowed_directories just like the
+    // dedicated add/remove_allowed_directory commands do, so it needs the
+    // same FsScope re-sync or frontend/plugin fs access drifts from policy.
+    if allowed_directories_changed {
+        security::sync_fs_scope(&app, &config.allowed_directories);
+    }
+
     Ok(())
 }
 
 #[tauri::command]
 pub async fn check_python_server(state: State<'_, AppState>) -> Result<bool, String> {
-    let url = format!("{}/health", state.python_server_url);
+    let base_url = state.python_server_url.lock().await.clone();
+    let url = format!("{}/health", base_url);
     let client = reqwest::Client::new();
 
     match client.get(&url).send().await {
@@ -146,24 +510,243 @@ pub async fn start_python_server(app: AppHandle) -> Result<(), String> {
         .map_err(|e| e.to_string())
 }
 
+/// A Python interpreter candidate: the executable plus any leading args
+/// needed to invoke it (e.g. the Windows `py` launcher's `-3`).
+#[derive(Debug, Clone)]
+struct PythonCandidate {
+    program: PathBuf,
+    args: Vec<String>,
+}
+
+impl PythonCandidate {
+    fn new(program: impl Into<PathBuf>) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+        }
+    }
+
+    fn with_args(program: impl Into<PathBuf>, args: &[&str]) -> Self {
+        Self {
+            program: program.into(),
+            args: args.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn display(&self) -> String {
+        if self.args.is_empty() {
+            self.program.display().to_string()
+        } else {
+            format!("{} {}", self.program.display(), self.args.join(" "))
+        }
+    }
+}
+
+/// The project-local virtualenv interpreter, if one has been set up under
+/// `python-agent/.venv`.
+fn venv_candidate(python_dir: &Path) -> Option<PythonCandidate> {
+    #[cfg(target_os = "windows")]
+    let venv_python = python_dir.join(".venv").join("Scripts").join("python.exe");
+    #[cfg(not(target_os = "windows"))]
+    let venv_python = python_dir.join(".venv").join("bin").join("python");
+
+    venv_python.is_file().then(|| PythonCandidate::new(venv_python))
+}
+
+/// Interpreters discoverable on `PATH`, including the Windows `py` launcher.
+fn path_candidates() -> Vec<PythonCandidate> {
+    let names: &[&str] = if cfg!(target_os = "windows") {
+        &["python", "python3"]
+    } else {
+        &["python3", "python"]
+    };
+
+    let path_var = std::env::var_os("PATH").unwrap_or_default();
+    let mut candidates = Vec::new();
+
+    for name in names {
+        let exe_name = if cfg!(target_os = "windows") {
+            format!("{}.exe", name)
+        } else {
+            name.to_string()
+        };
+
+        if let Some(found) = std::env::split_paths(&path_var)
+            .map(|dir| dir.join(&exe_name))
+            .find(|path| path.is_file())
+        {
+            candidates.push(PythonCandidate::new(found));
+        }
+    }
+
+    if cfg!(target_os = "windows") {
+        if let Some(py_launcher) = std::env::split_paths(&path_var)
+            .map(|dir| dir.join("py.exe"))
+            .find(|path| path.is_file())
+        {
+            candidates.push(PythonCandidate::with_args(py_launcher, &["-3"]));
+        }
+    }
+
+    candidates
+}
+
+/// Parse e.g. `"Python 3.11.4"` into `(3, 11)`.
+fn parse_python_version(output: &str) -> Option<(u32, u32)> {
+    let version_str = output.trim().strip_prefix("Python ")?;
+    let mut parts = version_str.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+/// Run `candidate --version` and return its version if it meets
+/// [`MIN_PYTHON_VERSION`].
+fn validate_candidate(candidate: &PythonCandidate) -> Option<(u32, u32)> {
+    let output = std::process::Command::new(&candidate.program)
+        .args(&candidate.args)
+        .arg("--version")
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    // Python 2 and some builds print the version to stderr rather than stdout.
+    let combined = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let version = parse_python_version(combined.trim())?;
+    (version >= MIN_PYTHON_VERSION).then_some(version)
+}
+
+/// Resolve a working Python interpreter: an explicit `Config.python_path`
+/// override first, then the `python-agent/.venv` interpreter, then the
+/// first qualifying interpreter found on `PATH`.
+fn resolve_python_interpreter(
+    python_dir: &Path,
+    override_path: Option<&PathBuf>,
+) -> Result<(PythonCandidate, (u32, u32))> {
+    if let Some(path) = override_path {
+        let candidate = PythonCandidate::new(path.clone());
+        return validate_candidate(&candidate)
+            .map(|version| (candidate, version))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Configured python_path {:?} is not a usable Python {}.{}+ interpreter",
+                    path,
+                    MIN_PYTHON_VERSION.0,
+                    MIN_PYTHON_VERSION.1
+                )
+            });
+    }
+
+    let mut candidates = Vec::new();
+    candidates.extend(venv_candidate(python_dir));
+    candidates.extend(path_candidates());
+
+    let tried: Vec<String> = candidates.iter().map(|c| c.display()).collect();
+
+    for candidate in candidates {
+        if let Some(version) = validate_candidate(&candidate) {
+            return Ok((candidate, version));
+        }
+    }
+
+    Err(anyhow::anyhow!(
+        "No Python {}.{}+ interpreter found. Tried: {}",
+        MIN_PYTHON_VERSION.0,
+        MIN_PYTHON_VERSION.1,
+        if tried.is_empty() {
+            "(nothing on PATH)".to_string()
+        } else {
+            tried.join(", ")
+        }
+    ))
+}
+
+#[tauri::command]
+pub async fn get_python_info(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<PythonInfo, String> {
+    let override_path = {
+        let config = state.config.lock().await;
+        config.python_path.clone()
+    };
+
+    let resource_path = app
+        .path_resolver()
+        .resource_dir()
+        .ok_or("Failed to resolve resource directory")?;
+    let python_dir = resource_path.join("python-agent");
+
+    let (candidate, version) = resolve_python_interpreter(&python_dir, override_path.as_ref())
+        .map_err(|e| e.to_string())?;
+
+    Ok(PythonInfo {
+        path: candidate.display(),
+        version: format!("{}.{}", version.0, version.1),
+    })
+}
+
+/// Find a loopback TCP port the OS considers free, handing it to the caller
+/// after dropping the listener. Re-checked once after release to guard
+/// against a racing process grabbing it (e.g. a socket still draining
+/// TIME_WAIT), retrying with a fresh allocation if that happens.
+fn find_free_port() -> Result<u16> {
+    const MAX_ATTEMPTS: u32 = 10;
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let port = listener.local_addr()?.port();
+        drop(listener);
+
+        if std::net::TcpListener::bind(("127.0.0.1", port)).is_ok() {
+            return Ok(port);
+        }
+
+        log::warn!(
+            "Port {} was taken immediately after allocation, retrying ({}/{})",
+            port,
+            attempt,
+            MAX_ATTEMPTS
+        );
+    }
+
+    Err(anyhow::anyhow!(
+        "Failed to find a free loopback port after {} attempts",
+        MAX_ATTEMPTS
+    ))
+}
+
 pub async fn ensure_python_server(app: &AppHandle) -> Result<()> {
     use std::process::Command;
     use std::time::Duration;
     use tokio::time::sleep;
 
-    // Check if server is already running
+    let state = app.state::<AppState>();
     let client = reqwest::Client::new();
+
+    // Check if a server we already know about is still running
+    let existing_url = state.python_server_url.lock().await.clone();
     if client
-        .get("http://localhost:8765/health")
+        .get(format!("{}/health", existing_url))
         .send()
         .await
         .is_ok()
     {
-        log::info!("Python server already running");
+        log::info!("Python server already running at {}", existing_url);
         return Ok(());
     }
 
-    log::info!("Starting Python server...");
+    let port = find_free_port()?;
+    let base_url = format!("http://127.0.0.1:{}", port);
+    log::info!("Starting Python server on {}...", base_url);
 
     // Get the resource path for the Python agent
     let resource_path = app
@@ -173,14 +756,19 @@ pub async fn ensure_python_server(app: &AppHandle) -> Result<()> {
 
     let python_dir = resource_path.join("python-agent");
 
-    // Start Python server as background process
-    #[cfg(target_os = "windows")]
-    let python_cmd = "python";
-    #[cfg(not(target_os = "windows"))]
-    let python_cmd = "python3";
+    let override_path = {
+        let config = state.config.lock().await;
+        config.python_path.clone()
+    };
+    let (python, version) = resolve_python_interpreter(&python_dir, override_path.as_ref())?;
+    log::info!("Using Python {}.{} at {}", version.0, version.1, python.display());
 
-    Command::new(python_cmd)
+    Command::new(&python.program)
+        .args(&python.args)
         .arg("server.py")
+        .arg("--port")
+        .arg(port.to_string())
+        .env("FILEBUDDY_PORT", port.to_string())
         .current_dir(&python_dir)
         .spawn()
         .map_err(|e| anyhow::anyhow!("Failed to start Python server: {}", e))?;
@@ -189,12 +777,13 @@ pub async fn ensure_python_server(app: &AppHandle) -> Result<()> {
     for _ in 0..30 {
         sleep(Duration::from_millis(500)).await;
         if client
-            .get("http://localhost:8765/health")
+            .get(format!("{}/health", base_url))
             .send()
             .await
             .is_ok()
         {
-            log::info!("Python server started successfully");
+            log::info!("Python server started successfully on {}", base_url);
+            *state.python_server_url.lock().await = base_url;
             return Ok(());
         }
     }
@@ -213,22 +802,32 @@ pub fn get_allowed_directories(state: State<'_, AppState>) -> Result<Vec<String>
 }
 
 #[tauri::command]
-pub fn add_allowed_directory(path: String, state: State<'_, AppState>) -> Result<(), String> {
+pub fn add_allowed_directory(
+    path: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
     let mut config = tauri::async_runtime::block_on(state.config.lock());
     let path_buf = PathBuf::from(&path);
     if !config.allowed_directories.contains(&path_buf) {
         config.allowed_directories.push(path_buf);
         config.save().map_err(|e| e.to_string())?;
     }
+    security::sync_fs_scope(&app, &config.allowed_directories);
     Ok(())
 }
 
 #[tauri::command]
-pub fn remove_allowed_directory(path: String, state: State<'_, AppState>) -> Result<(), String> {
+pub fn remove_allowed_directory(
+    path: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
     let mut config = tauri::async_runtime::block_on(state.config.lock());
     let path_buf = PathBuf::from(&path);
     config.allowed_directories.retain(|p| p != &path_buf);
     config.save().map_err(|e| e.to_string())?;
+    security::sync_fs_scope(&app, &config.allowed_directories);
     Ok(())
 }
 
@@ -15,6 +15,9 @@ pub struct Config {
     pub minimize_to_tray: bool,
     pub show_notifications: bool,
     pub memory_retention_days: u32,
+    /// Explicit interpreter path, overriding auto-detection when set.
+    #[serde(default)]
+    pub python_path: Option<PathBuf>,
 }
 
 impl Default for Config {
@@ -45,6 +48,7 @@ impl Default for Config {
             minimize_to_tray: true,
             show_notifications: true,
             memory_retention_days: 90,
+            python_path: None,
         }
     }
 }
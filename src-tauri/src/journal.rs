@@ -0,0 +1,336 @@
+use crate::config::Config;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A file moved aside by a `delete`/`remove` operation, kept in the trash
+/// directory until pruned or restored by undo.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedFile {
+    pub original_path: PathBuf,
+    pub trash_path: PathBuf,
+}
+
+/// A single journaled operation.
+///
+/// The journal file itself is append-only: undo does not edit a record in
+/// place, it appends a new one with the same `id` and `reverted: true`,
+/// which [`read_entries`] folds over so the latest write for an id wins.
+/// That makes a crash mid-undo safe to resume — [`revert`] only acts on
+/// files that are still where the original operation left them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub id: String,
+    pub command: String,
+    pub operation_type: String,
+    pub source_paths: Vec<PathBuf>,
+    pub target_paths: Vec<PathBuf>,
+    pub trashed: Vec<TrashedFile>,
+    pub timestamp: i64,
+    pub reverted: bool,
+}
+
+fn journal_path_in(base: &Path) -> PathBuf {
+    base.join("journal.jsonl")
+}
+
+fn journal_path() -> Result<PathBuf> {
+    Ok(journal_path_in(&Config::data_dir()?))
+}
+
+fn trash_dir_in(base: &Path) -> Result<PathBuf> {
+    let dir = base.join("trash");
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+pub fn trash_dir() -> Result<PathBuf> {
+    trash_dir_in(&Config::data_dir()?)
+}
+
+pub fn new_entry_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let counter = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("{:x}-{:x}", nanos, counter)
+}
+
+fn now_timestamp() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+/// Append `entry` as one JSON line. Safe to call concurrently with reads:
+/// other readers only ever see whole, newline-terminated lines.
+fn append_in(base: &Path, entry: &JournalEntry) -> Result<()> {
+    let path = journal_path_in(base);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let line = format!("{}\n", serde_json::to_string(entry)?);
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+fn append(entry: &JournalEntry) -> Result<()> {
+    append_in(&Config::data_dir()?, entry)
+}
+
+/// Read every journal line and fold by id, keeping the last write for each
+/// (an undo's `reverted: true` record supersedes the original). Malformed
+/// lines are logged and skipped rather than failing the whole read.
+fn read_entries_in(base: &Path) -> Result<Vec<JournalEntry>> {
+    let path = journal_path_in(base);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let reader = BufReader::new(fs::File::open(&path)?);
+    let mut by_id: HashMap<String, JournalEntry> = HashMap::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<JournalEntry>(&line) {
+            Ok(entry) => {
+                by_id.insert(entry.id.clone(), entry);
+            }
+            Err(e) => log::warn!("Skipping malformed journal line: {}", e),
+        }
+    }
+
+    let mut entries: Vec<JournalEntry> = by_id.into_values().collect();
+    entries.sort_by_key(|e| e.timestamp);
+    Ok(entries)
+}
+
+pub fn read_entries() -> Result<Vec<JournalEntry>> {
+    read_entries_in(&Config::data_dir()?)
+}
+
+/// Move `path` into the trash directory for journal entry `entry_id`,
+/// returning where it ended up.
+fn move_to_trash_in(base: &Path, entry_id: &str, path: &Path) -> Result<TrashedFile> {
+    let dest_dir = trash_dir_in(base)?.join(entry_id);
+    fs::create_dir_all(&dest_dir)?;
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("Path has no file name: {:?}", path))?;
+    let dest = dest_dir.join(file_name);
+
+    fs::rename(path, &dest)?;
+    Ok(TrashedFile {
+        original_path: path.to_path_buf(),
+        trash_path: dest,
+    })
+}
+
+pub fn move_to_trash(entry_id: &str, path: &Path) -> Result<TrashedFile> {
+    move_to_trash_in(&Config::data_dir()?, entry_id, path)
+}
+
+/// Append a journal entry for `operation_type` over `source_paths` (renamed
+/// or moved into `target_paths` when known, or with `trashed` files already
+/// moved aside). The entry exists only once this returns: the caller in
+/// `commands` appends it after the files have been trashed or the agent has
+/// finished the move, so a crash before that point leaves no entry.
+pub fn record(
+    id: &str,
+    command: &str,
+    operation_type: &str,
+    source_paths: Vec<PathBuf>,
+    target_paths: Vec<PathBuf>,
+    trashed: Vec<TrashedFile>,
+) -> Result<JournalEntry> {
+    let entry = JournalEntry {
+        id: id.to_string(),
+        command: command.to_string(),
+        operation_type: operation_type.to_string(),
+        source_paths,
+        target_paths,
+        trashed,
+        timestamp: now_timestamp(),
+        reverted: false,
+    };
+    append(&entry)?;
+    Ok(entry)
+}
+
+/// Revert a journal entry: restore trashed files to their original
+/// location, and move renamed/moved files back from their target to their
+/// source. Files already restored by a previous, interrupted undo are left
+/// alone, so calling this again on a partially undone entry is safe.
+fn revert_in(base: &Path, entry: &JournalEntry) -> Result<()> {
+    for trashed in &entry.trashed {
+        if trashed.trash_path.exists() && !trashed.original_path.exists() {
+            if let Some(parent) = trashed.original_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(&trashed.trash_path, &trashed.original_path)?;
+        }
+    }
+
+    for (source, target) in entry.source_paths.iter().zip(entry.target_paths.iter()) {
+        if target.exists() && !source.exists() {
+            if let Some(parent) = source.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(target, source)?;
+        }
+    }
+
+    let mut reverted_entry = entry.clone();
+    reverted_entry.reverted = true;
+    append_in(base, &reverted_entry)
+}
+
+pub fn revert(entry: &JournalEntry) -> Result<()> {
+    revert_in(&Config::data_dir()?, entry)
+}
+
+/// Drop journal entries (and any trashed files they reference) older than
+/// `retention_days`. Called on startup to honor `Config.memory_retention_days`.
+fn prune_older_than_in(base: &Path, retention_days: u32) -> Result<()> {
+    let cutoff = now_timestamp() - (retention_days as i64) * 24 * 60 * 60;
+    let entries = read_entries_in(base)?;
+
+    let (expired, kept): (Vec<_>, Vec<_>) = entries.into_iter().partition(|e| e.timestamp < cutoff);
+    if expired.is_empty() {
+        return Ok(());
+    }
+
+    for entry in &expired {
+        for trashed in &entry.trashed {
+            let _ = fs::remove_file(&trashed.trash_path);
+        }
+        let _ = fs::remove_dir_all(trash_dir_in(base)?.join(&entry.id));
+    }
+
+    rewrite_in(base, &kept)?;
+    log::info!(
+        "Pruned {} journal entries older than {} days",
+        expired.len(),
+        retention_days
+    );
+    Ok(())
+}
+
+pub fn prune_older_than(retention_days: u32) -> Result<()> {
+    prune_older_than_in(&Config::data_dir()?, retention_days)
+}
+
+/// Overwrite the journal with exactly `entries`. Used by pruning to compact
+/// the otherwise append-only log once entries age out.
+fn rewrite_in(base: &Path, entries: &[JournalEntry]) -> Result<()> {
+    let path = journal_path_in(base);
+    let mut file = fs::File::create(path)?;
+    for entry in entries {
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("filebuddy_test_journal_{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn entry_with(id: &str, timestamp: i64, trashed: Vec<TrashedFile>) -> JournalEntry {
+        JournalEntry {
+            id: id.to_string(),
+            command: "delete foo.txt".to_string(),
+            operation_type: "delete".to_string(),
+            source_paths: trashed.iter().map(|t| t.original_path.clone()).collect(),
+            target_paths: Vec::new(),
+            trashed,
+            timestamp,
+            reverted: false,
+        }
+    }
+
+    #[test]
+    fn test_move_to_trash_and_revert_round_trip() {
+        let base = test_dir("round_trip");
+        let original = base.join("foo.txt");
+        fs::write(&original, b"hello").unwrap();
+
+        let trashed = move_to_trash_in(&base, "entry-1", &original).unwrap();
+        assert!(!original.exists());
+        assert!(trashed.trash_path.exists());
+
+        let entry = entry_with("entry-1", 1, vec![trashed]);
+        revert_in(&base, &entry).unwrap();
+
+        assert!(original.exists());
+        assert_eq!(fs::read_to_string(&original).unwrap(), "hello");
+    }
+
+    #[test]
+    fn test_revert_is_noop_on_already_restored_entry() {
+        let base = test_dir("resume");
+        let original = base.join("foo.txt");
+        fs::write(&original, b"hello").unwrap();
+
+        let trashed = move_to_trash_in(&base, "entry-2", &original).unwrap();
+        let entry = entry_with("entry-2", 1, vec![trashed.clone()]);
+
+        // First revert moves the file out of the trash back to its original path.
+        revert_in(&base, &entry).unwrap();
+        assert!(original.exists());
+
+        // A second revert (e.g. resuming after a crash) must not touch the
+        // now-original file or error because the trash copy is already gone.
+        revert_in(&base, &entry).unwrap();
+        assert!(original.exists());
+        assert_eq!(fs::read_to_string(&original).unwrap(), "hello");
+    }
+
+    #[test]
+    fn test_prune_older_than_removes_expired_trash_and_journal_lines() {
+        let base = test_dir("prune");
+        let old_original = base.join("old.txt");
+        fs::write(&old_original, b"old").unwrap();
+        let new_original = base.join("new.txt");
+        fs::write(&new_original, b"new").unwrap();
+
+        let old_trashed = move_to_trash_in(&base, "old-entry", &old_original).unwrap();
+        let new_trashed = move_to_trash_in(&base, "new-entry", &new_original).unwrap();
+
+        let now = now_timestamp();
+        let old_entry = entry_with("old-entry", now - 200 * 24 * 60 * 60, vec![old_trashed.clone()]);
+        let new_entry = entry_with("new-entry", now, vec![new_trashed.clone()]);
+        append_in(&base, &old_entry).unwrap();
+        append_in(&base, &new_entry).unwrap();
+
+        prune_older_than_in(&base, 90).unwrap();
+
+        let remaining = read_entries_in(&base).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, "new-entry");
+
+        assert!(!old_trashed.trash_path.exists());
+        assert!(new_trashed.trash_path.exists());
+    }
+}
@@ -4,6 +4,7 @@
 mod commands;
 mod config;
 mod file_ops;
+mod journal;
 mod security;
 mod tray;
 
@@ -12,12 +13,17 @@ use tauri::{
     SystemTrayMenuItem, WindowEvent,
 };
 use log::{info, error};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use global_hotkey::{hotkey::HotKey, GlobalHotKeyManager};
 
 pub struct AppState {
     pub config: Arc<Mutex<config::Config>>,
-    pub python_server_url: String,
+    pub python_server_url: Arc<Mutex<String>>,
+    pub hotkey_manager: Arc<Mutex<Option<GlobalHotKeyManager>>>,
+    pub current_hotkey: Arc<Mutex<Option<HotKey>>>,
+    pub pending_confirmations: Arc<Mutex<HashMap<String, commands::PendingOperation>>>,
 }
 
 fn main() {
@@ -45,7 +51,10 @@ fn main() {
     let config = config::Config::load().unwrap_or_default();
     let app_state = AppState {
         config: Arc::new(Mutex::new(config)),
-        python_server_url: "http://localhost:8765".to_string(),
+        python_server_url: Arc::new(Mutex::new("http://127.0.0.1:8765".to_string())),
+        hotkey_manager: Arc::new(Mutex::new(None)),
+        current_hotkey: Arc::new(Mutex::new(None)),
+        pending_confirmations: Arc::new(Mutex::new(HashMap::new())),
     };
 
     tauri::Builder::default()
@@ -90,12 +99,16 @@ fn main() {
         })
         .invoke_handler(tauri::generate_handler![
             commands::execute_voice_command,
+            commands::preview_voice_command,
+            commands::confirm_operation,
+            commands::cancel_operation,
             commands::get_operation_history,
             commands::undo_operation,
             commands::get_settings,
             commands::update_settings,
             commands::check_python_server,
             commands::start_python_server,
+            commands::get_python_info,
             commands::get_allowed_directories,
             commands::add_allowed_directory,
             commands::remove_allowed_directory,
@@ -103,15 +116,29 @@ fn main() {
             commands::get_system_info,
         ])
         .setup(|app| {
-            // Start Python server in background
             let app_handle = app.handle();
+
+            // Add allowed_directories to Tauri's own FsScope so plugins and
+            // frontend fs access are bound by the allowed set, and prune
+            // journal entries/trash older than the configured retention.
+            {
+                let state = app_handle.state::<AppState>();
+                let config = tauri::async_runtime::block_on(state.config.lock());
+                security::sync_fs_scope(&app_handle, &config.allowed_directories);
+                if let Err(e) = journal::prune_older_than(config.memory_retention_days) {
+                    error!("Failed to prune operation journal: {}", e);
+                }
+            }
+
+            // Start Python server in background
+            let server_handle = app_handle.clone();
             tauri::async_runtime::spawn(async move {
-                if let Err(e) = commands::ensure_python_server(&app_handle).await {
+                if let Err(e) = commands::ensure_python_server(&server_handle).await {
                     error!("Failed to start Python server: {}", e);
                 }
             });
 
-            // Register global hotkey (Ctrl+Shift+F)
+            // Register the configured global hotkey (rebindable at runtime via update_settings)
             tray::register_hotkey(app.handle());
 
             Ok(())
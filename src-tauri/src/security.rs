@@ -1,4 +1,5 @@
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Manager};
 
 /// Forbidden paths that should never be accessible
 pub fn get_forbidden_paths() -> Vec<&'static str> {
@@ -19,25 +20,73 @@ pub fn get_forbidden_paths() -> Vec<&'static str> {
     ]
 }
 
+/// True if any resolved path component matches a forbidden basename exactly
+/// (`.ssh`, `.env`, ...), or the resolved path sits under one of the
+/// absolute forbidden roots (`/System`, `C:\Windows`, ...) component-by-
+/// component. This replaces a plain substring check, which both missed
+/// symlink escapes and false-positived on names that merely contain a
+/// forbidden string (a `bin-scripts` folder, a `my.env.example` file).
+fn is_forbidden(resolved: &Path) -> bool {
+    for forbidden in get_forbidden_paths() {
+        let is_absolute_root = forbidden.starts_with('/') || forbidden.contains(":\\");
+
+        if is_absolute_root {
+            if has_component_prefix(resolved, Path::new(forbidden)) {
+                return true;
+            }
+        } else {
+            let forbidden_lower = forbidden.to_lowercase();
+            let matches_component = resolved.components().any(|component| {
+                component_name(component).is_some_and(|name| name.to_lowercase() == forbidden_lower)
+            });
+            if matches_component {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+fn component_name(component: Component) -> Option<std::borrow::Cow<'_, str>> {
+    match component {
+        Component::Normal(name) => Some(name.to_string_lossy()),
+        _ => None,
+    }
+}
+
+/// Whether `path`'s components start with `prefix`'s components, compared
+/// case-insensitively (so `C:\Windows` matches on case-insensitive filesystems).
+fn has_component_prefix(path: &Path, prefix: &Path) -> bool {
+    let path_components: Vec<_> = path.components().collect();
+    let prefix_components: Vec<_> = prefix.components().collect();
+
+    if prefix_components.len() > path_components.len() {
+        return false;
+    }
+
+    path_components
+        .iter()
+        .zip(prefix_components.iter())
+        .all(|(p, q)| {
+            p.as_os_str().to_string_lossy().to_lowercase() == q.as_os_str().to_string_lossy().to_lowercase()
+        })
+}
+
 /// Validate if a path is safe to access
 pub fn validate_path(path: &Path, allowed_directories: &[PathBuf]) -> bool {
-    // Resolve the path to get absolute path
+    // Resolve the path to get absolute path, following symlinks so a link
+    // inside an allowed directory can't point somewhere forbidden.
     let resolved = match path.canonicalize() {
         Ok(p) => p,
         Err(_) => return false, // Path doesn't exist or can't be accessed
     };
 
-    // Check against forbidden paths
-    let path_str = resolved.to_string_lossy().to_lowercase();
-    for forbidden in get_forbidden_paths() {
-        let forbidden_lower = forbidden.to_lowercase();
-        if path_str.contains(&forbidden_lower) {
-            log::warn!("Blocked access to forbidden path: {:?}", resolved);
-            return false;
-        }
+    if is_forbidden(&resolved) {
+        log::warn!("Blocked access to forbidden path: {:?}", resolved);
+        return false;
     }
 
-    // Check if path is within allowed directories
+    // Check if the fully-resolved path is within a fully-resolved allowed directory
     for allowed_dir in allowed_directories {
         let allowed_resolved = match allowed_dir.canonicalize() {
             Ok(p) => p,
@@ -53,6 +102,109 @@ pub fn validate_path(path: &Path, allowed_directories: &[PathBuf]) -> bool {
     false
 }
 
+/// Like [`validate_path`], but for a move/rename *destination*, which
+/// typically doesn't exist yet and so can't be `canonicalize`d directly.
+/// Resolves the destination's parent directory instead (following symlinks,
+/// same as `validate_path`), then checks the still-to-be-created leaf
+/// against the forbidden list and the resolved parent against the allowed
+/// directories.
+pub fn validate_destination_path(path: &Path, allowed_directories: &[PathBuf]) -> bool {
+    let parent = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => return false,
+    };
+    let file_name = match path.file_name() {
+        Some(n) => n,
+        None => return false,
+    };
+
+    let resolved_parent = match parent.canonicalize() {
+        Ok(p) => p,
+        Err(_) => return false, // Destination directory doesn't exist or can't be accessed
+    };
+    let resolved = resolved_parent.join(file_name);
+
+    if is_forbidden(&resolved) {
+        log::warn!("Blocked move/rename into forbidden path: {:?}", resolved);
+        return false;
+    }
+
+    for allowed_dir in allowed_directories {
+        let allowed_resolved = match allowed_dir.canonicalize() {
+            Ok(p) => p,
+            Err(_) => continue,
+        };
+
+        if resolved_parent.starts_with(&allowed_resolved) {
+            return true;
+        }
+    }
+
+    log::warn!("Destination not in allowed directories: {:?}", resolved);
+    false
+}
+
+/// Add `allowed_directories` to Tauri's own `FsScope`, so frontend code and
+/// plugins that go through Tauri's fs APIs are bound by the allowed set.
+/// Call on startup and whenever the allowed set changes.
+///
+/// Each allowed directory is allowed recursively. The absolute forbidden
+/// roots are forbidden as single paths, and the forbidden names (`.ssh`,
+/// `.env`, ...) only where they sit directly inside an allowed directory;
+/// deeper matches are not forbidden in the scope, unlike [`validate_path`].
+/// Only allow rules are added: a directory dropped from the allowed set
+/// stays allowed in the scope for the rest of the session.
+pub fn sync_fs_scope(app_handle: &AppHandle, allowed_directories: &[PathBuf]) {
+    let scope = app_handle.fs_scope();
+
+    for dir in allowed_directories {
+        let canonical = match dir.canonicalize() {
+            Ok(p) => p,
+            Err(_) => continue,
+        };
+        if let Err(e) = scope.allow_directory(&canonical, true) {
+            log::warn!("Failed to allow {:?} in fs scope: {}", canonical, e);
+        }
+    }
+
+    for forbidden in get_forbidden_paths() {
+        let is_absolute_root = forbidden.starts_with('/') || forbidden.contains(":\\");
+
+        if is_absolute_root {
+            let forbidden_path = PathBuf::from(forbidden);
+            if forbidden_path.exists() {
+                if let Err(e) = scope.forbid_file(&forbidden_path) {
+                    log::warn!("Failed to forbid {:?} in fs scope: {}", forbidden_path, e);
+                }
+            }
+            continue;
+        }
+
+        // Relative entries (`.ssh`, `.env`, ...) only mean anything nested
+        // under a directory we've actually allowed, so resolve each one
+        // against every allowed directory instead of the process cwd —
+        // otherwise the check below is checking a path nobody ever grants
+        // fs-scope access to in the first place.
+        for dir in allowed_directories {
+            let Ok(allowed_resolved) = dir.canonicalize() else {
+                continue;
+            };
+            let Ok(resolved) = allowed_resolved.join(forbidden).canonicalize() else {
+                continue;
+            };
+
+            let result = if resolved.is_dir() {
+                scope.forbid_directory(&resolved, true)
+            } else {
+                scope.forbid_file(&resolved)
+            };
+            if let Err(e) = result {
+                log::warn!("Failed to forbid {:?} in fs scope: {}", resolved, e);
+            }
+        }
+    }
+}
+
 /// Check if a path is a system-critical directory
 pub fn is_system_critical(path: &Path) -> bool {
     let critical_dirs = vec![
@@ -140,4 +292,58 @@ mod tests {
             RiskLevel::Critical
         );
     }
+
+    #[test]
+    fn test_component_match_rejects_false_positive_substrings() {
+        let base = std::env::temp_dir().join("filebuddy_test_component_match");
+        let _ = std::fs::remove_dir_all(&base);
+        std::fs::create_dir_all(&base).unwrap();
+
+        // ".configuration" contains ".config" as a substring but is not the
+        // forbidden ".config" component, so it must stay allowed.
+        let safe_dir = base.join(".configuration");
+        std::fs::create_dir_all(&safe_dir).unwrap();
+
+        let allowed = vec![base.clone()];
+        assert!(validate_path(&safe_dir, &allowed));
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_exact_forbidden_component_is_blocked() {
+        let base = std::env::temp_dir().join("filebuddy_test_forbidden_component");
+        let _ = std::fs::remove_dir_all(&base);
+        std::fs::create_dir_all(&base).unwrap();
+
+        let ssh_dir = base.join(".ssh");
+        std::fs::create_dir_all(&ssh_dir).unwrap();
+
+        let allowed = vec![base.clone()];
+        assert!(!validate_path(&ssh_dir, &allowed));
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_escape_is_blocked() {
+        let base = std::env::temp_dir().join("filebuddy_test_symlink_base");
+        let outside = std::env::temp_dir().join("filebuddy_test_symlink_outside");
+        let _ = std::fs::remove_dir_all(&base);
+        let _ = std::fs::remove_dir_all(&outside);
+        std::fs::create_dir_all(&base).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+
+        let link = base.join("escape");
+        std::os::unix::fs::symlink(&outside, &link).unwrap();
+
+        // The symlink lives inside the allowed directory, but resolves
+        // outside it, so it must be rejected.
+        let allowed = vec![base.clone()];
+        assert!(!validate_path(&link, &allowed));
+
+        std::fs::remove_dir_all(&base).unwrap();
+        std::fs::remove_dir_all(&outside).unwrap();
+    }
 }
\ No newline at end of file
@@ -3,7 +3,13 @@ use global_hotkey::{
     hotkey::{Code, HotKey, Modifiers},
     GlobalHotKeyEvent, GlobalHotKeyManager,
 };
-use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::AppState;
+
+/// Minimum gap between two accepted hotkey events. A held chord can repeat
+/// faster than a user intends to trigger voice activation twice.
+const HOTKEY_DEBOUNCE: Duration = Duration::from_millis(300);
 
 pub fn register_hotkey(app_handle: AppHandle) {
     tauri::async_runtime::spawn(async move {
@@ -16,47 +22,205 @@ pub fn register_hotkey(app_handle: AppHandle) {
 async fn setup_hotkey(app_handle: AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     let manager = GlobalHotKeyManager::new()?;
 
-    // Register Ctrl+Shift+F
-    let hotkey = HotKey::new(
-        Some(Modifiers::CONTROL | Modifiers::SHIFT),
-        Code::KeyF,
-    );
+    let state = app_handle.state::<AppState>();
+    let hotkey_str = {
+        let config = state.config.lock().await;
+        config.hotkey.clone()
+    };
+
+    let hotkey = match parse_hotkey(&hotkey_str) {
+        Ok(hotkey) => hotkey,
+        Err(e) => {
+            log::warn!(
+                "Failed to parse configured hotkey {:?} ({}), falling back to Ctrl+Shift+F",
+                hotkey_str,
+                e
+            );
+            parse_hotkey("Ctrl+Shift+F").expect("default hotkey must parse")
+        }
+    };
 
     manager.register(hotkey)?;
+    log::info!("Registered global hotkey: {:?}", hotkey);
 
-    log::info!("Registered global hotkey: Ctrl+Shift+F");
+    *state.current_hotkey.lock().await = Some(hotkey);
+    *state.hotkey_manager.lock().await = Some(manager);
 
     // Listen for hotkey events
     let receiver = GlobalHotKeyEvent::receiver();
+    let app_handle = app_handle.clone();
 
     tauri::async_runtime::spawn(async move {
+        let mut last_fired: Option<Instant> = None;
+
         loop {
             if let Ok(event) = receiver.try_recv() {
-                log::debug!("Hotkey pressed: {:?}", event);
-                
-                // Show the main window and trigger voice activation
-                if let Some(window) = app_handle.get_window("main") {
-                    if let Err(e) = window.show() {
-                        log::error!("Failed to show window: {}", e);
-                    }
-                    if let Err(e) = window.set_focus() {
-                        log::error!("Failed to focus window: {}", e);
-                    }
-                    
-                    // Emit event to frontend to start voice listening
-                    if let Err(e) = window.emit("hotkey-activated", {}) {
-                        log::error!("Failed to emit hotkey event: {}", e);
+                let now = Instant::now();
+                let is_bounce = last_fired
+                    .map(|t| now.duration_since(t) < HOTKEY_DEBOUNCE)
+                    .unwrap_or(false);
+
+                if !is_bounce {
+                    last_fired = Some(now);
+                    log::debug!("Hotkey pressed: {:?}", event);
+
+                    // Show the main window and trigger voice activation
+                    if let Some(window) = app_handle.get_window("main") {
+                        if let Err(e) = window.show() {
+                            log::error!("Failed to show window: {}", e);
+                        }
+                        if let Err(e) = window.set_focus() {
+                            log::error!("Failed to focus window: {}", e);
+                        }
+
+                        // Emit event to frontend to start voice listening
+                        if let Err(e) = window.emit("hotkey-activated", {}) {
+                            log::error!("Failed to emit hotkey event: {}", e);
+                        }
                     }
                 }
             }
-            
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+            tokio::time::sleep(Duration::from_millis(100)).await;
         }
     });
 
     Ok(())
 }
 
+/// Unregister the currently active hotkey (if any) and register `new_hotkey_str`
+/// in its place, so a rebind from the Settings UI takes effect immediately.
+///
+/// On failure the previous binding is restored and left active.
+pub async fn update_hotkey(app_handle: &AppHandle, new_hotkey_str: &str) -> Result<(), String> {
+    let new_hotkey = parse_hotkey(new_hotkey_str)?;
+
+    let state = app_handle.state::<AppState>();
+    let manager_guard = state.hotkey_manager.lock().await;
+    let manager = manager_guard
+        .as_ref()
+        .ok_or_else(|| "Hotkey manager is not initialized yet".to_string())?;
+
+    let mut current = state.current_hotkey.lock().await;
+
+    if *current == Some(new_hotkey) {
+        return Ok(());
+    }
+
+    if let Some(old_hotkey) = *current {
+        if let Err(e) = manager.unregister(old_hotkey) {
+            log::warn!("Failed to unregister previous hotkey: {}", e);
+        }
+    }
+
+    if let Err(e) = manager.register(new_hotkey) {
+        // Keep the app usable: put the previous binding back.
+        if let Some(old_hotkey) = *current {
+            let _ = manager.register(old_hotkey);
+        }
+        return Err(format!(
+            "Failed to register hotkey {:?}: {}",
+            new_hotkey_str, e
+        ));
+    }
+
+    *current = Some(new_hotkey);
+    log::info!("Hotkey rebound to {:?}", new_hotkey_str);
+    Ok(())
+}
+
+/// Parse a hotkey string such as `"Ctrl+Shift+F"` into a [`HotKey`].
+///
+/// Splits on `+`, trims whitespace, and matches modifier names
+/// case-insensitively. The final token must be a single letter, digit, or
+/// function key (`F1`..`F24`).
+pub fn parse_hotkey(spec: &str) -> Result<HotKey, String> {
+    let tokens: Vec<&str> = spec
+        .split('+')
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    if tokens.is_empty() {
+        return Err(format!("Empty hotkey string: {:?}", spec));
+    }
+
+    let mut modifiers = Modifiers::empty();
+    let mut code = None;
+    let last = tokens.len() - 1;
+
+    for (i, token) in tokens.iter().enumerate() {
+        match token.to_lowercase().as_str() {
+            "ctrl" | "control" => modifiers |= Modifiers::CONTROL,
+            "shift" => modifiers |= Modifiers::SHIFT,
+            "alt" | "option" => modifiers |= Modifiers::ALT,
+            "cmd" | "super" | "meta" | "win" => modifiers |= Modifiers::SUPER,
+            key if i == last => code = Some(parse_code(key)?),
+            other => return Err(format!("Unknown hotkey token: {:?}", other)),
+        }
+    }
+
+    let code = code.ok_or_else(|| format!("Hotkey {:?} is missing a key token", spec))?;
+    Ok(HotKey::new(Some(modifiers), code))
+}
+
+/// Map the trailing token of a hotkey spec (e.g. `"F"`, `"1"`, `"F5"`) to a [`Code`].
+fn parse_code(token: &str) -> Result<Code, String> {
+    let upper = token.to_uppercase();
+
+    if let Some(rest) = upper.strip_prefix('F') {
+        if let Ok(n) = rest.parse::<u8>() {
+            return function_key_code(n).ok_or_else(|| format!("Unknown function key: {:?}", token));
+        }
+    }
+
+    if upper.len() == 1 {
+        let ch = upper.chars().next().unwrap();
+        if let Some(code) = letter_code(ch) {
+            return Ok(code);
+        }
+        if let Some(code) = digit_code(ch) {
+            return Ok(code);
+        }
+    }
+
+    Err(format!("Unrecognized key token: {:?}", token))
+}
+
+fn letter_code(ch: char) -> Option<Code> {
+    Some(match ch {
+        'A' => Code::KeyA, 'B' => Code::KeyB, 'C' => Code::KeyC, 'D' => Code::KeyD,
+        'E' => Code::KeyE, 'F' => Code::KeyF, 'G' => Code::KeyG, 'H' => Code::KeyH,
+        'I' => Code::KeyI, 'J' => Code::KeyJ, 'K' => Code::KeyK, 'L' => Code::KeyL,
+        'M' => Code::KeyM, 'N' => Code::KeyN, 'O' => Code::KeyO, 'P' => Code::KeyP,
+        'Q' => Code::KeyQ, 'R' => Code::KeyR, 'S' => Code::KeyS, 'T' => Code::KeyT,
+        'U' => Code::KeyU, 'V' => Code::KeyV, 'W' => Code::KeyW, 'X' => Code::KeyX,
+        'Y' => Code::KeyY, 'Z' => Code::KeyZ,
+        _ => return None,
+    })
+}
+
+fn digit_code(ch: char) -> Option<Code> {
+    Some(match ch {
+        '0' => Code::Digit0, '1' => Code::Digit1, '2' => Code::Digit2, '3' => Code::Digit3,
+        '4' => Code::Digit4, '5' => Code::Digit5, '6' => Code::Digit6, '7' => Code::Digit7,
+        '8' => Code::Digit8, '9' => Code::Digit9,
+        _ => return None,
+    })
+}
+
+fn function_key_code(n: u8) -> Option<Code> {
+    Some(match n {
+        1 => Code::F1, 2 => Code::F2, 3 => Code::F3, 4 => Code::F4,
+        5 => Code::F5, 6 => Code::F6, 7 => Code::F7, 8 => Code::F8,
+        9 => Code::F9, 10 => Code::F10, 11 => Code::F11, 12 => Code::F12,
+        13 => Code::F13, 14 => Code::F14, 15 => Code::F15, 16 => Code::F16,
+        17 => Code::F17, 18 => Code::F18, 19 => Code::F19, 20 => Code::F20,
+        21 => Code::F21, 22 => Code::F22, 23 => Code::F23, 24 => Code::F24,
+        _ => return None,
+    })
+}
+
 pub fn update_tray_status(app_handle: &AppHandle, status: &str) {
     if let Some(tray) = app_handle.tray_handle() {
         use tauri::{CustomMenuItem, SystemTrayMenu, SystemTrayMenuItem};
@@ -78,4 +242,39 @@ pub fn update_tray_status(app_handle: &AppHandle, status: &str) {
             log::error!("Failed to update tray menu: {}", e);
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_default_hotkey() {
+        let hotkey = parse_hotkey("Ctrl+Shift+F").unwrap();
+        assert_eq!(hotkey, HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyF));
+    }
+
+    #[test]
+    fn test_parse_is_case_insensitive() {
+        let hotkey = parse_hotkey("ctrl+shift+f").unwrap();
+        assert_eq!(hotkey, HotKey::new(Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyF));
+    }
+
+    #[test]
+    fn test_parse_digit_and_function_key() {
+        assert_eq!(
+            parse_hotkey("Alt+1").unwrap(),
+            HotKey::new(Some(Modifiers::ALT), Code::Digit1)
+        );
+        assert_eq!(
+            parse_hotkey("Cmd+F5").unwrap(),
+            HotKey::new(Some(Modifiers::SUPER), Code::F5)
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_token() {
+        assert!(parse_hotkey("Ctrl+Banana").is_err());
+        assert!(parse_hotkey("").is_err());
+    }
+}